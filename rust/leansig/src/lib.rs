@@ -1,19 +1,50 @@
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use leansig::signature::SignatureScheme;
-use leansig::signature::generalized_xmss::instantiations_poseidon_top_level::lifetime_2_to_the_32::hashing_optimized::SIGTopLevelTargetSumLifetime32Dim64Base8;
 use rand::{SeedableRng,rngs::StdRng};
+use std::ffi::CStr;
+use std::os::raw::c_char;
 use std::ptr;
+use std::slice;
+use zeroize::{Zeroize, Zeroizing};
 
-pub type LeanSignatureScheme = SIGTopLevelTargetSumLifetime32Dim64Base8;
-pub type LeanPublicKey = <LeanSignatureScheme as SignatureScheme>::PublicKey;
-pub type LeanSecretKey = <LeanSignatureScheme as SignatureScheme>::SecretKey;
+mod scheme;
+mod signing_state;
 
+pub use scheme::SchemeId;
+pub use signing_state::SigningState;
 
-pub struct SecretKey {
-    pub inner: LeanSecretKey,
+use scheme::{Scheme18, Scheme20, Scheme32};
+
+/// Fixed-length message digest consumed by `sign`/`verify`. The underlying
+/// XMSS instantiations all sign a hash output rather than an arbitrary-length
+/// message and share the same digest width regardless of `SchemeId`, so FFI
+/// callers must pass exactly this many bytes no matter which scheme they
+/// selected at key-generation time.
+pub const MESSAGE_LENGTH: usize = <Scheme32 as SignatureScheme>::MESSAGE_LENGTH;
+
+/// Holds secret-key material as its own canonical-compressed byte encoding
+/// rather than the upstream typed `SecretKey`, so that zeroizing it is a
+/// guarantee this crate can actually make: these bytes are the sole,
+/// crate-owned copy of the key while it is idle, and `Zeroizing<Vec<u8>>`
+/// (not an unverified upstream `Zeroize` impl) is what overwrites them
+/// before the backing allocation is freed. The typed secret key is
+/// reconstructed transiently for the duration of a single `sign` call.
+pub enum SecretKey {
+    Lifetime18Dim64Base8(Zeroizing<Vec<u8>>),
+    Lifetime20Dim64Base8(Zeroizing<Vec<u8>>),
+    Lifetime32Dim64Base8(Zeroizing<Vec<u8>>),
+}
+
+pub enum PublicKey {
+    Lifetime18Dim64Base8(<Scheme18 as SignatureScheme>::PublicKey),
+    Lifetime20Dim64Base8(<Scheme20 as SignatureScheme>::PublicKey),
+    Lifetime32Dim64Base8(<Scheme32 as SignatureScheme>::PublicKey),
 }
 
-pub struct PublicKey {
-    pub inner: LeanPublicKey,
+pub enum Signature {
+    Lifetime18Dim64Base8(<Scheme18 as SignatureScheme>::Signature),
+    Lifetime20Dim64Base8(<Scheme20 as SignatureScheme>::Signature),
+    Lifetime32Dim64Base8(<Scheme32 as SignatureScheme>::Signature),
 }
 
 pub struct Keypair {
@@ -21,43 +52,242 @@ pub struct Keypair {
     pub secret_key: SecretKey,
 }
 
+impl SecretKey {
+    pub fn scheme_id(&self) -> SchemeId {
+        match self {
+            SecretKey::Lifetime18Dim64Base8(_) => SchemeId::Lifetime18Dim64Base8,
+            SecretKey::Lifetime20Dim64Base8(_) => SchemeId::Lifetime20Dim64Base8,
+            SecretKey::Lifetime32Dim64Base8(_) => SchemeId::Lifetime32Dim64Base8,
+        }
+    }
+
+    /// Overwrites this key's stored bytes (the full canonical encoding,
+    /// seed/PRF material included) with zeros via `Zeroizing`'s
+    /// volatile-write-backed `Zeroize` impl, so casual memory inspection
+    /// after the backing allocation is freed yields no key material.
+    ///
+    /// Best-effort, following the secp256k1 precedent: the compiler may
+    /// still have left copies elsewhere (register spills, moved-from stack
+    /// slots, swapped pages, or the transient typed key reconstructed during
+    /// a `sign` call) that this call cannot reach.
+    pub fn zeroize(&mut self) {
+        match self {
+            SecretKey::Lifetime18Dim64Base8(bytes) => bytes.zeroize(),
+            SecretKey::Lifetime20Dim64Base8(bytes) => bytes.zeroize(),
+            SecretKey::Lifetime32Dim64Base8(bytes) => bytes.zeroize(),
+        }
+    }
+}
+
+// No manual `Drop` impl is needed: each variant's `Zeroizing<Vec<u8>>`
+// already zeroizes its buffer in its own `Drop` before deallocating, so the
+// guarantee holds whenever a `SecretKey` (or the `Keypair` containing it) is
+// dropped, including via `leansig_keypair_free`.
+
 impl PublicKey {
-    pub fn new(inner: LeanPublicKey) -> Self {
-        Self { inner }
+    pub fn scheme_id(&self) -> SchemeId {
+        match self {
+            PublicKey::Lifetime18Dim64Base8(_) => SchemeId::Lifetime18Dim64Base8,
+            PublicKey::Lifetime20Dim64Base8(_) => SchemeId::Lifetime20Dim64Base8,
+            PublicKey::Lifetime32Dim64Base8(_) => SchemeId::Lifetime32Dim64Base8,
+        }
     }
 }
 
-impl SecretKey {
-    pub fn new(inner: LeanSecretKey) -> Self {
-        Self { inner }
+impl Signature {
+    pub fn scheme_id(&self) -> SchemeId {
+        match self {
+            Signature::Lifetime18Dim64Base8(_) => SchemeId::Lifetime18Dim64Base8,
+            Signature::Lifetime20Dim64Base8(_) => SchemeId::Lifetime20Dim64Base8,
+            Signature::Lifetime32Dim64Base8(_) => SchemeId::Lifetime32Dim64Base8,
+        }
     }
 }
 
-/// FFI: Exposed for Go (cgo) interoperability.
+/// Copies a caller-provided `(ptr, len)` buffer into a fixed-size message
+/// digest, returning `None` if `len` does not match `MESSAGE_LENGTH`.
 ///
 /// # Safety
-/// - `ptr` must be a valid pointer to `len` bytes.
-/// - Caller is responsible for freeing returned memory.
+/// `ptr` must be valid for reads of `len` bytes.
+unsafe fn read_message(ptr: *const u8, len: usize) -> Option<[u8; MESSAGE_LENGTH]> {
+    if ptr.is_null() || len != MESSAGE_LENGTH {
+        return None;
+    }
+
+    let bytes = unsafe { slice::from_raw_parts(ptr, len) };
+    let mut message = [0u8; MESSAGE_LENGTH];
+    message.copy_from_slice(bytes);
+    Some(message)
+}
+
+/// Serializes `value` into the caller-provided `(out_ptr, out_len)` buffer.
+///
+/// Follows the secp256k1 length-query convention: callers may pass a null
+/// `out_ptr` (or an `out_len` too small to fit the encoding) to learn the
+/// required size via `written_len` without writing anything. Returns `true`
+/// only when the full encoding was written.
+///
+/// # Safety
+/// `out_ptr` must be valid for writes of `out_len` bytes (unless null), and
+/// `written_len` must be valid for a single `usize` write.
+unsafe fn serialize_into<T: CanonicalSerialize>(
+    value: &T,
+    out_ptr: *mut u8,
+    out_len: usize,
+    written_len: *mut usize,
+) -> bool {
+    let needed = value.compressed_size();
+
+    if !written_len.is_null() {
+        unsafe { *written_len = needed };
+    }
+
+    if out_ptr.is_null() || out_len < needed {
+        return false;
+    }
+
+    let out = unsafe { slice::from_raw_parts_mut(out_ptr, needed) };
+    value.serialize_compressed(out).is_ok()
+}
 
+/// Deserializes a `T` from a caller-provided `(ptr, len)` buffer, returning
+/// `None` on a null pointer or malformed encoding.
+///
+/// # Safety
+/// `ptr` must be valid for reads of `len` bytes.
+unsafe fn deserialize_from<T: CanonicalDeserialize>(ptr: *const u8, len: usize) -> Option<T> {
+    if ptr.is_null() {
+        return None;
+    }
+
+    let bytes = unsafe { slice::from_raw_parts(ptr, len) };
+    T::deserialize_compressed(bytes).ok()
+}
+
+/// Serializes a freshly generated secret key into an owned, zero-on-drop
+/// byte buffer. This is the only representation `SecretKey` stores.
+pub(crate) fn secret_key_to_bytes<T: CanonicalSerialize>(sk: &T) -> Zeroizing<Vec<u8>> {
+    let mut bytes = vec![0u8; sk.compressed_size()];
+    sk.serialize_compressed(bytes.as_mut_slice())
+        .expect("serializing a freshly generated secret key cannot fail");
+    Zeroizing::new(bytes)
+}
+
+/// Reconstructs the typed secret key from its stored bytes, for the
+/// duration of a single call; the caller is responsible for not retaining
+/// the result any longer than necessary.
+pub(crate) fn secret_key_from_bytes<T: CanonicalDeserialize>(bytes: &[u8]) -> Option<T> {
+    T::deserialize_compressed(bytes).ok()
+}
+
+/// Copies a stored byte buffer into the caller-provided `(out_ptr, out_len)`
+/// buffer, following the same length-query convention as `serialize_into`.
+///
+/// # Safety
+/// `out_ptr` must be valid for writes of `out_len` bytes (unless null), and
+/// `written_len` must be valid for a single `usize` write.
+unsafe fn copy_bytes_into(
+    bytes: &[u8],
+    out_ptr: *mut u8,
+    out_len: usize,
+    written_len: *mut usize,
+) -> bool {
+    if !written_len.is_null() {
+        unsafe { *written_len = bytes.len() };
+    }
+
+    if out_ptr.is_null() || out_len < bytes.len() {
+        return false;
+    }
+
+    let out = unsafe { slice::from_raw_parts_mut(out_ptr, bytes.len()) };
+    out.copy_from_slice(bytes);
+    true
+}
+
+/// Validates that `ptr`/`len` decode to a well-formed `T`, then returns the
+/// raw bytes (not `T` itself) so the caller can store them directly as a
+/// `SecretKey`'s owned, zero-on-drop representation.
+///
+/// # Safety
+/// `ptr` must be valid for reads of `len` bytes.
+unsafe fn validated_secret_key_bytes<T: CanonicalDeserialize>(
+    ptr: *const u8,
+    len: usize,
+) -> Option<Zeroizing<Vec<u8>>> {
+    if ptr.is_null() {
+        return None;
+    }
+
+    let bytes = unsafe { slice::from_raw_parts(ptr, len) };
+    T::deserialize_compressed(bytes).ok()?;
+    Some(Zeroizing::new(bytes.to_vec()))
+}
+
+/// Checks whether `epoch` falls within `sk`'s active window
+/// `[activation_epoch, activation_epoch + num_active_epochs)`. Signing
+/// outside this window is meaningless (and, depending on the instantiation,
+/// may panic), so callers must gate on this before calling `sign`.
+fn epoch_in_window<S: SignatureScheme>(sk: &S::SecretKey, epoch: usize) -> bool {
+    let activation_epoch = S::activation_epoch(sk);
+    let num_active_epochs = S::num_active_epochs(sk);
+    epoch >= activation_epoch && epoch < activation_epoch + num_active_epochs
+}
+
+/// FFI: Generates a `Keypair` under the given `SchemeId`, deterministically
+/// from `seed`, active over the epoch window
+/// `[activation_epoch, activation_epoch + num_active_epochs)`.
+///
+/// `scheme` only selects a lifetime (see `SchemeId`); dimension and base are
+/// fixed at `Dim64Base8` for every variant. A deployment wanting a different
+/// dimension/base trade-off needs a new `SchemeId` variant, not a
+/// recompile-time choice.
+///
+/// # Safety
+/// - Caller is responsible for freeing the returned `Keypair` via
+///   `leansig_keypair_free`.
+/// - Returns null if `scheme` is not a recognized `SchemeId` discriminant.
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn leansig_keypair_generate(
+    scheme: u32,
     seed: u64,
     activation_epoch: usize,
     num_active_epochs: usize,
 ) -> *mut Keypair {
+    let Some(scheme) = SchemeId::from_raw(scheme) else {
+        return ptr::null_mut();
+    };
+
     let mut rng = StdRng::seed_from_u64(seed);
-    
-    let (pk, sk) = <LeanSignatureScheme as SignatureScheme>::key_gen(&mut rng, activation_epoch, num_active_epochs);
-    
-    let public_key = PublicKey::new(pk);
-    let secret_key = SecretKey::new(sk);
-    
-    let keypair = Box::new(Keypair {
-        public_key,
-        secret_key,
-    });
-    
-    Box::into_raw(keypair)
+
+    let keypair = match scheme {
+        SchemeId::Lifetime18Dim64Base8 => {
+            let (pk, sk) =
+                <Scheme18 as SignatureScheme>::key_gen(&mut rng, activation_epoch, num_active_epochs);
+            Keypair {
+                public_key: PublicKey::Lifetime18Dim64Base8(pk),
+                secret_key: SecretKey::Lifetime18Dim64Base8(secret_key_to_bytes(&sk)),
+            }
+        }
+        SchemeId::Lifetime20Dim64Base8 => {
+            let (pk, sk) =
+                <Scheme20 as SignatureScheme>::key_gen(&mut rng, activation_epoch, num_active_epochs);
+            Keypair {
+                public_key: PublicKey::Lifetime20Dim64Base8(pk),
+                secret_key: SecretKey::Lifetime20Dim64Base8(secret_key_to_bytes(&sk)),
+            }
+        }
+        SchemeId::Lifetime32Dim64Base8 => {
+            let (pk, sk) =
+                <Scheme32 as SignatureScheme>::key_gen(&mut rng, activation_epoch, num_active_epochs);
+            Keypair {
+                public_key: PublicKey::Lifetime32Dim64Base8(pk),
+                secret_key: SecretKey::Lifetime32Dim64Base8(secret_key_to_bytes(&sk)),
+            }
+        }
+    };
+
+    Box::into_raw(Box::new(keypair))
 }
 
 // Get a pointer to the public key from a keypair
@@ -66,7 +296,7 @@ pub unsafe extern "C" fn leansig_keypair_get_public_key(keypair: *const Keypair)
     if keypair.is_null() {
            return ptr::null();
     }
-    
+
     unsafe {
          &(*keypair).public_key
     }
@@ -78,7 +308,7 @@ pub unsafe extern "C" fn leansig_keypair_get_private_key(keypair: *const Keypair
     if keypair.is_null() {
            return ptr::null();
     }
-    
+
     unsafe {
          &(*keypair).secret_key
     }
@@ -97,6 +327,9 @@ pub unsafe extern "C" fn leansig_keypair_get_private_key(keypair: *const Keypair
 /// # Notes
 /// - This function is intended for use from Go or other languages via FFI.
 /// - It converts the raw pointer back into a `Box` and drops it, freeing the memory.
+/// - Dropping the `SecretKey` zeroizes its underlying bytes first (see
+///   `SecretKey::zeroize`), so the freed memory does not linger with key
+///   material in it.
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn leansig_keypair_free(key_pair: *mut Keypair) {
     if !key_pair.is_null() {
@@ -104,4 +337,655 @@ pub unsafe extern "C" fn leansig_keypair_free(key_pair: *mut Keypair) {
             let _ = Box::from_raw(key_pair);
         }
     }
-}
\ No newline at end of file
+}
+
+/// FFI: Returns the `SchemeId` discriminant a `PublicKey` was produced under.
+///
+/// # Safety
+/// `public_key` must be a valid pointer previously returned by
+/// `leansig_keypair_get_public_key` or `leansig_public_key_deserialize`.
+/// Returns `u32::MAX` (not a valid `SchemeId` discriminant) if `public_key`
+/// is null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn leansig_public_key_scheme(public_key: *const PublicKey) -> u32 {
+    if public_key.is_null() {
+        return u32::MAX;
+    }
+
+    unsafe { &*public_key }.scheme_id().as_raw()
+}
+
+/// FFI: Returns the `SchemeId` discriminant a `SecretKey` was produced under.
+///
+/// # Safety
+/// `secret_key` must be a valid pointer previously returned by
+/// `leansig_keypair_get_private_key` or `leansig_secret_key_deserialize`.
+/// Returns `u32::MAX` (not a valid `SchemeId` discriminant) if `secret_key`
+/// is null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn leansig_secret_key_scheme(secret_key: *const SecretKey) -> u32 {
+    if secret_key.is_null() {
+        return u32::MAX;
+    }
+
+    unsafe { &*secret_key }.scheme_id().as_raw()
+}
+
+/// FFI: Zeroizes a `SecretKey`'s underlying bytes in place, without freeing
+/// the allocation. Useful when a caller wants to scrub key material as soon
+/// as it is done signing but will free the pointer later (e.g. `free` is
+/// also called implicitly by `leansig_keypair_free`).
+///
+/// # Safety
+/// - `secret_key` must be a valid pointer previously returned by
+///   `leansig_keypair_get_private_key` or `leansig_secret_key_deserialize`.
+/// - Passing a null pointer is safe (function does nothing).
+/// - The `SecretKey` must not be used for signing after this call; its
+///   contents are zeroed, not merely marked invalid.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn leansig_secret_key_zeroize(secret_key: *mut SecretKey) {
+    if !secret_key.is_null() {
+        unsafe { &mut *secret_key }.zeroize();
+    }
+}
+
+/// FFI: Returns the `SchemeId` discriminant a `Signature` was produced under.
+///
+/// # Safety
+/// `signature` must be a valid pointer previously returned by `leansig_sign`
+/// or `leansig_signature_deserialize`. Returns `u32::MAX` (not a valid
+/// `SchemeId` discriminant) if `signature` is null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn leansig_signature_scheme(signature: *const Signature) -> u32 {
+    if signature.is_null() {
+        return u32::MAX;
+    }
+
+    unsafe { &*signature }.scheme_id().as_raw()
+}
+
+/// FFI: Signs a `MESSAGE_LENGTH`-byte digest under the given epoch, returning
+/// a heap-allocated `Signature` tagged with the same `SchemeId` as
+/// `secret_key`.
+///
+/// # Safety
+/// - `secret_key` must be a valid pointer previously returned by
+///   `leansig_keypair_generate` (via `leansig_keypair_get_private_key`) or
+///   by `leansig_secret_key_deserialize`.
+/// - `msg_ptr` must be valid for reads of `msg_len` bytes.
+/// - Returns null if `secret_key`/`msg_ptr` is null, `msg_len` does not equal
+///   `MESSAGE_LENGTH`, or `epoch` is outside the key's active window.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn leansig_sign(
+    secret_key: *const SecretKey,
+    epoch: usize,
+    msg_ptr: *const u8,
+    msg_len: usize,
+) -> *mut Signature {
+    if secret_key.is_null() {
+        return ptr::null_mut();
+    }
+
+    let message = match unsafe { read_message(msg_ptr, msg_len) } {
+        Some(message) => message,
+        None => return ptr::null_mut(),
+    };
+
+    let sig = match unsafe { &*secret_key } {
+        SecretKey::Lifetime18Dim64Base8(bytes) => {
+            let sk: <Scheme18 as SignatureScheme>::SecretKey = match secret_key_from_bytes(bytes) {
+                Some(sk) => sk,
+                None => return ptr::null_mut(),
+            };
+            if !epoch_in_window::<Scheme18>(&sk, epoch) {
+                return ptr::null_mut();
+            }
+            Signature::Lifetime18Dim64Base8(<Scheme18 as SignatureScheme>::sign(&sk, epoch, &message))
+        }
+        SecretKey::Lifetime20Dim64Base8(bytes) => {
+            let sk: <Scheme20 as SignatureScheme>::SecretKey = match secret_key_from_bytes(bytes) {
+                Some(sk) => sk,
+                None => return ptr::null_mut(),
+            };
+            if !epoch_in_window::<Scheme20>(&sk, epoch) {
+                return ptr::null_mut();
+            }
+            Signature::Lifetime20Dim64Base8(<Scheme20 as SignatureScheme>::sign(&sk, epoch, &message))
+        }
+        SecretKey::Lifetime32Dim64Base8(bytes) => {
+            let sk: <Scheme32 as SignatureScheme>::SecretKey = match secret_key_from_bytes(bytes) {
+                Some(sk) => sk,
+                None => return ptr::null_mut(),
+            };
+            if !epoch_in_window::<Scheme32>(&sk, epoch) {
+                return ptr::null_mut();
+            }
+            Signature::Lifetime32Dim64Base8(<Scheme32 as SignatureScheme>::sign(&sk, epoch, &message))
+        }
+    };
+
+    Box::into_raw(Box::new(sig))
+}
+
+/// FFI: Verifies a `Signature` against a `MESSAGE_LENGTH`-byte digest and
+/// epoch.
+///
+/// # Safety
+/// - `public_key` must be a valid pointer previously returned by
+///   `leansig_keypair_generate` (via `leansig_keypair_get_public_key`) or
+///   by `leansig_public_key_deserialize`.
+/// - `msg_ptr` must be valid for reads of `msg_len` bytes.
+/// - `signature` must be a valid pointer previously returned by
+///   `leansig_sign`, `leansig_signing_state_sign` or
+///   `leansig_signature_deserialize`.
+/// - Returns `false` if any pointer is null, `msg_len` does not equal
+///   `MESSAGE_LENGTH`, or `public_key` and `signature` were produced under
+///   different `SchemeId`s, rather than panicking.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn leansig_verify(
+    public_key: *const PublicKey,
+    epoch: usize,
+    msg_ptr: *const u8,
+    msg_len: usize,
+    signature: *const Signature,
+) -> bool {
+    if public_key.is_null() || signature.is_null() {
+        return false;
+    }
+
+    let message = match unsafe { read_message(msg_ptr, msg_len) } {
+        Some(message) => message,
+        None => return false,
+    };
+
+    match (unsafe { &*public_key }, unsafe { &*signature }) {
+        (PublicKey::Lifetime18Dim64Base8(pk), Signature::Lifetime18Dim64Base8(sig)) => {
+            <Scheme18 as SignatureScheme>::verify(pk, epoch, &message, sig)
+        }
+        (PublicKey::Lifetime20Dim64Base8(pk), Signature::Lifetime20Dim64Base8(sig)) => {
+            <Scheme20 as SignatureScheme>::verify(pk, epoch, &message, sig)
+        }
+        (PublicKey::Lifetime32Dim64Base8(pk), Signature::Lifetime32Dim64Base8(sig)) => {
+            <Scheme32 as SignatureScheme>::verify(pk, epoch, &message, sig)
+        }
+        _ => false,
+    }
+}
+
+/// FFI: Frees a heap-allocated `Signature` returned by `leansig_sign`,
+/// `leansig_signing_state_sign` or `leansig_signature_deserialize`.
+///
+/// # Safety
+/// - `signature` must be a pointer previously returned by `leansig_sign`,
+///   `leansig_signing_state_sign` or `leansig_signature_deserialize`.
+/// - Passing a null pointer is safe (function does nothing).
+/// - After calling this function, the pointer must not be used again.
+/// - Must only be called once per allocated `Signature` to avoid double-free.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn leansig_signature_free(signature: *mut Signature) {
+    if !signature.is_null() {
+        unsafe {
+            let _ = Box::from_raw(signature);
+        }
+    }
+}
+
+/// FFI: Serializes a `PublicKey` into `out_ptr`. See `serialize_into` for the
+/// length-query convention. The `SchemeId` is not encoded in the bytes
+/// themselves; callers must record it (e.g. via `leansig_public_key_scheme`)
+/// alongside the serialized buffer to pass back into
+/// `leansig_public_key_deserialize`.
+///
+/// # Safety
+/// - `public_key` must be a valid pointer previously returned by
+///   `leansig_keypair_get_public_key` or `leansig_public_key_deserialize`.
+/// - `out_ptr` must be valid for writes of `out_len` bytes (unless null).
+/// - `written_len` must be valid for a single `usize` write.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn leansig_public_key_serialize(
+    public_key: *const PublicKey,
+    out_ptr: *mut u8,
+    out_len: usize,
+    written_len: *mut usize,
+) -> bool {
+    if public_key.is_null() {
+        return false;
+    }
+
+    match unsafe { &*public_key } {
+        PublicKey::Lifetime18Dim64Base8(pk) => unsafe {
+            serialize_into(pk, out_ptr, out_len, written_len)
+        },
+        PublicKey::Lifetime20Dim64Base8(pk) => unsafe {
+            serialize_into(pk, out_ptr, out_len, written_len)
+        },
+        PublicKey::Lifetime32Dim64Base8(pk) => unsafe {
+            serialize_into(pk, out_ptr, out_len, written_len)
+        },
+    }
+}
+
+/// FFI: Reconstructs a heap-allocated `PublicKey` from a byte buffer
+/// previously produced by `leansig_public_key_serialize`, under the given
+/// `SchemeId`.
+///
+/// # Safety
+/// `ptr` must be valid for reads of `len` bytes. Returns null if `scheme` is
+/// not a recognized `SchemeId` discriminant or the encoding is malformed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn leansig_public_key_deserialize(
+    scheme: u32,
+    ptr: *const u8,
+    len: usize,
+) -> *mut PublicKey {
+    let public_key = match SchemeId::from_raw(scheme) {
+        Some(SchemeId::Lifetime18Dim64Base8) => unsafe {
+            deserialize_from(ptr, len).map(PublicKey::Lifetime18Dim64Base8)
+        },
+        Some(SchemeId::Lifetime20Dim64Base8) => unsafe {
+            deserialize_from(ptr, len).map(PublicKey::Lifetime20Dim64Base8)
+        },
+        Some(SchemeId::Lifetime32Dim64Base8) => unsafe {
+            deserialize_from(ptr, len).map(PublicKey::Lifetime32Dim64Base8)
+        },
+        None => None,
+    };
+
+    match public_key {
+        Some(public_key) => Box::into_raw(Box::new(public_key)),
+        None => ptr::null_mut(),
+    }
+}
+
+/// FFI: Frees a heap-allocated `PublicKey` returned by
+/// `leansig_public_key_deserialize`.
+///
+/// # Safety
+/// - `public_key` must be a pointer previously returned by
+///   `leansig_public_key_deserialize`, not a borrow returned by
+///   `leansig_keypair_get_public_key` (that one is owned by its `Keypair`
+///   and freed via `leansig_keypair_free`).
+/// - Passing a null pointer is safe (function does nothing).
+/// - After calling this function, the pointer must not be used again.
+/// - Must only be called once per allocated `PublicKey` to avoid double-free.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn leansig_public_key_free(public_key: *mut PublicKey) {
+    if !public_key.is_null() {
+        unsafe {
+            let _ = Box::from_raw(public_key);
+        }
+    }
+}
+
+/// FFI: Serializes a `SecretKey` into `out_ptr`. See `serialize_into` for the
+/// length-query convention. The `SchemeId` is not encoded in the bytes
+/// themselves; callers must record it (e.g. via `leansig_secret_key_scheme`)
+/// alongside the serialized buffer to pass back into
+/// `leansig_secret_key_deserialize`.
+///
+/// # Safety
+/// - `secret_key` must be a valid pointer previously returned by
+///   `leansig_keypair_get_private_key` or `leansig_secret_key_deserialize`.
+/// - `out_ptr` must be valid for writes of `out_len` bytes (unless null).
+/// - `written_len` must be valid for a single `usize` write.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn leansig_secret_key_serialize(
+    secret_key: *const SecretKey,
+    out_ptr: *mut u8,
+    out_len: usize,
+    written_len: *mut usize,
+) -> bool {
+    if secret_key.is_null() {
+        return false;
+    }
+
+    match unsafe { &*secret_key } {
+        SecretKey::Lifetime18Dim64Base8(bytes) => unsafe {
+            copy_bytes_into(bytes, out_ptr, out_len, written_len)
+        },
+        SecretKey::Lifetime20Dim64Base8(bytes) => unsafe {
+            copy_bytes_into(bytes, out_ptr, out_len, written_len)
+        },
+        SecretKey::Lifetime32Dim64Base8(bytes) => unsafe {
+            copy_bytes_into(bytes, out_ptr, out_len, written_len)
+        },
+    }
+}
+
+/// FFI: Reconstructs a heap-allocated `SecretKey` from a byte buffer
+/// previously produced by `leansig_secret_key_serialize`, under the given
+/// `SchemeId`.
+///
+/// # Safety
+/// `ptr` must be valid for reads of `len` bytes. Returns null if `scheme` is
+/// not a recognized `SchemeId` discriminant or the encoding is malformed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn leansig_secret_key_deserialize(
+    scheme: u32,
+    ptr: *const u8,
+    len: usize,
+) -> *mut SecretKey {
+    let secret_key = match SchemeId::from_raw(scheme) {
+        Some(SchemeId::Lifetime18Dim64Base8) => unsafe {
+            validated_secret_key_bytes::<<Scheme18 as SignatureScheme>::SecretKey>(ptr, len)
+                .map(SecretKey::Lifetime18Dim64Base8)
+        },
+        Some(SchemeId::Lifetime20Dim64Base8) => unsafe {
+            validated_secret_key_bytes::<<Scheme20 as SignatureScheme>::SecretKey>(ptr, len)
+                .map(SecretKey::Lifetime20Dim64Base8)
+        },
+        Some(SchemeId::Lifetime32Dim64Base8) => unsafe {
+            validated_secret_key_bytes::<<Scheme32 as SignatureScheme>::SecretKey>(ptr, len)
+                .map(SecretKey::Lifetime32Dim64Base8)
+        },
+        None => None,
+    };
+
+    match secret_key {
+        Some(secret_key) => Box::into_raw(Box::new(secret_key)),
+        None => ptr::null_mut(),
+    }
+}
+
+/// FFI: Zeroizes and frees a heap-allocated `SecretKey` returned by
+/// `leansig_secret_key_deserialize`.
+///
+/// A standalone deserialized `SecretKey` used only with the stateless
+/// `leansig_sign` never passes through `leansig_signing_state_open`, so this
+/// is the only way to reclaim (and scrub) it. Do not call this on the borrow
+/// returned by `leansig_keypair_get_private_key`; that one is owned by its
+/// `Keypair` and freed (and zeroized) via `leansig_keypair_free`.
+///
+/// # Safety
+/// - `secret_key` must be a pointer previously returned by
+///   `leansig_secret_key_deserialize`.
+/// - Passing a null pointer is safe (function does nothing).
+/// - After calling this function, the pointer must not be used again.
+/// - Must only be called once per allocated `SecretKey` to avoid double-free.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn leansig_secret_key_free(secret_key: *mut SecretKey) {
+    if !secret_key.is_null() {
+        unsafe {
+            let mut secret_key = Box::from_raw(secret_key);
+            secret_key.zeroize();
+        }
+    }
+}
+
+/// FFI: Serializes a `Signature` into `out_ptr`. See `serialize_into` for the
+/// length-query convention. The `SchemeId` is not encoded in the bytes
+/// themselves; callers must record it (e.g. via `leansig_signature_scheme`)
+/// alongside the serialized buffer to pass back into
+/// `leansig_signature_deserialize`.
+///
+/// # Safety
+/// - `signature` must be a valid pointer previously returned by
+///   `leansig_sign`, `leansig_signing_state_sign` or
+///   `leansig_signature_deserialize`.
+/// - `out_ptr` must be valid for writes of `out_len` bytes (unless null).
+/// - `written_len` must be valid for a single `usize` write.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn leansig_signature_serialize(
+    signature: *const Signature,
+    out_ptr: *mut u8,
+    out_len: usize,
+    written_len: *mut usize,
+) -> bool {
+    if signature.is_null() {
+        return false;
+    }
+
+    match unsafe { &*signature } {
+        Signature::Lifetime18Dim64Base8(sig) => unsafe {
+            serialize_into(sig, out_ptr, out_len, written_len)
+        },
+        Signature::Lifetime20Dim64Base8(sig) => unsafe {
+            serialize_into(sig, out_ptr, out_len, written_len)
+        },
+        Signature::Lifetime32Dim64Base8(sig) => unsafe {
+            serialize_into(sig, out_ptr, out_len, written_len)
+        },
+    }
+}
+
+/// FFI: Reconstructs a heap-allocated `Signature` from a byte buffer
+/// previously produced by `leansig_signature_serialize`, under the given
+/// `SchemeId`.
+///
+/// # Safety
+/// `ptr` must be valid for reads of `len` bytes. Returns null if `scheme` is
+/// not a recognized `SchemeId` discriminant or the encoding is malformed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn leansig_signature_deserialize(
+    scheme: u32,
+    ptr: *const u8,
+    len: usize,
+) -> *mut Signature {
+    let signature = match SchemeId::from_raw(scheme) {
+        Some(SchemeId::Lifetime18Dim64Base8) => unsafe {
+            deserialize_from(ptr, len).map(Signature::Lifetime18Dim64Base8)
+        },
+        Some(SchemeId::Lifetime20Dim64Base8) => unsafe {
+            deserialize_from(ptr, len).map(Signature::Lifetime20Dim64Base8)
+        },
+        Some(SchemeId::Lifetime32Dim64Base8) => unsafe {
+            deserialize_from(ptr, len).map(Signature::Lifetime32Dim64Base8)
+        },
+        None => None,
+    };
+
+    match signature {
+        Some(signature) => Box::into_raw(Box::new(signature)),
+        None => ptr::null_mut(),
+    }
+}
+
+/// FFI: Opens (creating if absent) a durable `SigningState` journal at
+/// `path`, taking ownership of `secret_key`.
+///
+/// # Safety
+/// - `path` must be a valid, nul-terminated UTF-8 C string.
+/// - `secret_key` must be a pointer previously returned by
+///   `leansig_secret_key_deserialize`, or otherwise owned by the caller and
+///   not freed separately afterwards.
+/// - `secret_key` is consumed unconditionally by this call, including on
+///   failure: once `leansig_signing_state_open` returns, whether or not the
+///   result is null, the pointer is invalid and must not be read, freed, or
+///   passed to another function.
+/// - Returns null if `path`/`secret_key` is null, `path` is not valid UTF-8,
+///   the journal file cannot be opened, or the journal is already held open
+///   by another `SigningState` (see `SigningState::open`).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn leansig_signing_state_open(
+    path: *const c_char,
+    secret_key: *mut SecretKey,
+) -> *mut SigningState {
+    if secret_key.is_null() {
+        return ptr::null_mut();
+    }
+
+    // Take ownership first, so the key is consumed (and zeroized on drop)
+    // on every failure path below as well.
+    let secret_key = unsafe { *Box::from_raw(secret_key) };
+
+    if path.is_null() {
+        return ptr::null_mut();
+    }
+
+    let path = match unsafe { CStr::from_ptr(path) }.to_str() {
+        Ok(path) => path,
+        Err(_) => return ptr::null_mut(),
+    };
+
+    match SigningState::open(path, secret_key) {
+        Ok(state) => Box::into_raw(Box::new(state)),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// FFI: Durably reserves up to `count` additional epochs in a single fsync,
+/// returning the number actually reserved (which may be less than `count`,
+/// or zero, once the key's active window is exhausted).
+///
+/// # Safety
+/// `state` must be a valid pointer previously returned by
+/// `leansig_signing_state_open`. Returns 0 if `state` is null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn leansig_signing_state_reserve(
+    state: *mut SigningState,
+    count: usize,
+) -> usize {
+    if state.is_null() {
+        return 0;
+    }
+
+    unsafe { &mut *state }.reserve(count).unwrap_or(0)
+}
+
+/// FFI: Signs a `MESSAGE_LENGTH`-byte digest under the next unused epoch,
+/// auto-reserving (and `fsync`-ing) a fresh batch of epochs first if none is
+/// already durably reserved.
+///
+/// # Safety
+/// - `state` must be a valid pointer previously returned by
+///   `leansig_signing_state_open`.
+/// - `msg_ptr` must be valid for reads of `msg_len` bytes.
+/// - Returns null if `state`/`msg_ptr` is null, `msg_len` does not equal
+///   `MESSAGE_LENGTH`, the active epoch window is exhausted, or the journal
+///   write failed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn leansig_signing_state_sign(
+    state: *mut SigningState,
+    msg_ptr: *const u8,
+    msg_len: usize,
+) -> *mut Signature {
+    if state.is_null() {
+        return ptr::null_mut();
+    }
+
+    let message = match unsafe { read_message(msg_ptr, msg_len) } {
+        Some(message) => message,
+        None => return ptr::null_mut(),
+    };
+
+    match unsafe { &mut *state }.sign(&message) {
+        Ok(sig) => Box::into_raw(Box::new(sig)),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// FFI: Frees a heap-allocated `SigningState` returned by
+/// `leansig_signing_state_open`, along with the `SecretKey` it owns.
+///
+/// # Safety
+/// - `state` must be a pointer previously returned by
+///   `leansig_signing_state_open`.
+/// - Passing a null pointer is safe (function does nothing).
+/// - After calling this function, the pointer must not be used again.
+/// - Must only be called once per allocated `SigningState` to avoid
+///   double-free.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn leansig_signing_state_free(state: *mut SigningState) {
+    if !state.is_null() {
+        unsafe {
+            let _ = Box::from_raw(state);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Queries the required length from `serialize`, then fills a buffer of
+    /// exactly that size, mirroring how a real FFI caller uses the
+    /// length-query convention documented on `serialize_into`.
+    unsafe fn serialized_bytes(serialize: impl Fn(*mut u8, usize, *mut usize) -> bool) -> Vec<u8> {
+        let mut needed = 0usize;
+        assert!(!serialize(ptr::null_mut(), 0, &mut needed));
+
+        let mut buf = vec![0u8; needed];
+        assert!(serialize(buf.as_mut_ptr(), buf.len(), &mut needed));
+        buf
+    }
+
+    #[test]
+    fn public_key_secret_key_and_signature_round_trip_over_the_wire() {
+        let keypair =
+            unsafe { leansig_keypair_generate(SchemeId::Lifetime18Dim64Base8.as_raw(), 7, 0, 16) };
+        assert!(!keypair.is_null());
+
+        let public_key = unsafe { leansig_keypair_get_public_key(keypair) };
+        let secret_key = unsafe { leansig_keypair_get_private_key(keypair) };
+
+        let pk_scheme = unsafe { leansig_public_key_scheme(public_key) };
+        let pk_bytes = unsafe {
+            serialized_bytes(|out_ptr, out_len, written_len| {
+                leansig_public_key_serialize(public_key, out_ptr, out_len, written_len)
+            })
+        };
+        let deserialized_pk = unsafe { leansig_public_key_deserialize(pk_scheme, pk_bytes.as_ptr(), pk_bytes.len()) };
+        assert!(!deserialized_pk.is_null());
+        let pk_bytes_again = unsafe {
+            serialized_bytes(|out_ptr, out_len, written_len| {
+                leansig_public_key_serialize(deserialized_pk, out_ptr, out_len, written_len)
+            })
+        };
+        assert_eq!(pk_bytes, pk_bytes_again, "public key must round-trip exactly");
+
+        let sk_scheme = unsafe { leansig_secret_key_scheme(secret_key) };
+        let sk_bytes = unsafe {
+            serialized_bytes(|out_ptr, out_len, written_len| {
+                leansig_secret_key_serialize(secret_key, out_ptr, out_len, written_len)
+            })
+        };
+        let deserialized_sk =
+            unsafe { leansig_secret_key_deserialize(sk_scheme, sk_bytes.as_ptr(), sk_bytes.len()) };
+        assert!(!deserialized_sk.is_null());
+        let sk_bytes_again = unsafe {
+            serialized_bytes(|out_ptr, out_len, written_len| {
+                leansig_secret_key_serialize(deserialized_sk, out_ptr, out_len, written_len)
+            })
+        };
+        assert_eq!(sk_bytes, sk_bytes_again, "secret key must round-trip exactly");
+
+        let message = [0u8; MESSAGE_LENGTH];
+        let signature =
+            unsafe { leansig_sign(deserialized_sk, 0, message.as_ptr(), message.len()) };
+        assert!(!signature.is_null());
+
+        let sig_scheme = unsafe { leansig_signature_scheme(signature) };
+        let sig_bytes = unsafe {
+            serialized_bytes(|out_ptr, out_len, written_len| {
+                leansig_signature_serialize(signature, out_ptr, out_len, written_len)
+            })
+        };
+        let deserialized_sig =
+            unsafe { leansig_signature_deserialize(sig_scheme, sig_bytes.as_ptr(), sig_bytes.len()) };
+        assert!(!deserialized_sig.is_null());
+        let sig_bytes_again = unsafe {
+            serialized_bytes(|out_ptr, out_len, written_len| {
+                leansig_signature_serialize(deserialized_sig, out_ptr, out_len, written_len)
+            })
+        };
+        assert_eq!(sig_bytes, sig_bytes_again, "signature must round-trip exactly");
+
+        assert!(unsafe {
+            leansig_verify(
+                deserialized_pk,
+                0,
+                message.as_ptr(),
+                message.len(),
+                deserialized_sig,
+            )
+        });
+
+        unsafe {
+            leansig_signature_free(signature);
+            leansig_signature_free(deserialized_sig);
+            leansig_public_key_free(deserialized_pk);
+            leansig_secret_key_free(deserialized_sk);
+            leansig_keypair_free(keypair);
+        }
+    }
+}
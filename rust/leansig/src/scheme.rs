@@ -0,0 +1,47 @@
+use leansig::signature::generalized_xmss::instantiations_poseidon_top_level::lifetime_2_to_the_18::hashing_optimized::SIGTopLevelTargetSumLifetime18Dim64Base8;
+use leansig::signature::generalized_xmss::instantiations_poseidon_top_level::lifetime_2_to_the_20::hashing_optimized::SIGTopLevelTargetSumLifetime20Dim64Base8;
+use leansig::signature::generalized_xmss::instantiations_poseidon_top_level::lifetime_2_to_the_32::hashing_optimized::SIGTopLevelTargetSumLifetime32Dim64Base8;
+
+/// The `generalized_xmss` instantiation used for a 2^18-epoch lifetime.
+pub type Scheme18 = SIGTopLevelTargetSumLifetime18Dim64Base8;
+/// The `generalized_xmss` instantiation used for a 2^20-epoch lifetime.
+pub type Scheme20 = SIGTopLevelTargetSumLifetime20Dim64Base8;
+/// The `generalized_xmss` instantiation used for a 2^32-epoch lifetime. This
+/// was the only instantiation available before `SchemeId` existed.
+pub type Scheme32 = SIGTopLevelTargetSumLifetime32Dim64Base8;
+
+/// Identifies which `instantiations_poseidon_top_level` variant a `Keypair`,
+/// `PublicKey`, `SecretKey`, or `Signature` was produced under.
+///
+/// Threaded through the FFI (mirroring secp256k1's context-flag selection)
+/// so one shared library can serve deployments that want a shorter lifetime
+/// without recompiling. Only the lifetime is selectable: every variant uses
+/// dimension 64 and base 8, and no other dimension/base choice is offered.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemeId {
+    /// 2^18 epochs; shortest lifetime, cheapest signing/verification.
+    Lifetime18Dim64Base8 = 0,
+    /// 2^20 epochs.
+    Lifetime20Dim64Base8 = 1,
+    /// 2^32 epochs; longest lifetime.
+    Lifetime32Dim64Base8 = 2,
+}
+
+impl SchemeId {
+    /// Converts a raw FFI discriminant into a `SchemeId`, or `None` if it is
+    /// out of range.
+    pub fn from_raw(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(Self::Lifetime18Dim64Base8),
+            1 => Some(Self::Lifetime20Dim64Base8),
+            2 => Some(Self::Lifetime32Dim64Base8),
+            _ => None,
+        }
+    }
+
+    /// The raw FFI discriminant for this scheme.
+    pub fn as_raw(self) -> u32 {
+        self as u32
+    }
+}
@@ -0,0 +1,294 @@
+use crate::scheme::{Scheme18, Scheme20, Scheme32};
+use crate::{MESSAGE_LENGTH, SecretKey, Signature, secret_key_from_bytes};
+use leansig::signature::SignatureScheme;
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+/// How many epochs to durably reserve per fsync. Reserved-but-unused epochs
+/// at the tail of a batch are burned (never handed out) if the process exits
+/// before consuming them; this trades a small amount of key lifetime for
+/// avoiding an fsync on every signature.
+const RESERVATION_BATCH: usize = 64;
+
+/// Wraps a `SecretKey` with a durable, monotonic record of which epochs have
+/// already been handed out for signing.
+///
+/// Because every signature under this (generalized XMSS) scheme consumes a
+/// distinct one-time epoch, signing two messages under the same epoch can
+/// leak enough of the one-time secret to forge further signatures. A
+/// `SigningState` enforces the write-ahead discipline required to make that
+/// safe across crashes: before epoch `e` is ever handed to `sign`, the
+/// journal on disk is updated and `fsync`'d to record "epochs <= e are
+/// spent", so a crash after that point can at worst waste a few reserved
+/// epochs, never reuse one.
+///
+/// `open` takes an exclusive lock on the journal file, so only one
+/// `SigningState` may have a given path open at a time: two openers racing
+/// on the same journal would defeat the crash-consistency guarantee above by
+/// both reserving (and handing out) the same epochs.
+pub struct SigningState {
+    secret_key: SecretKey,
+    activation_epoch: usize,
+    num_active_epochs: usize,
+    /// Durably persisted: the first epoch that has not yet been reserved.
+    reserved_up_to: usize,
+    /// In-memory: the first epoch that has not yet been handed out to a
+    /// caller. Always `<= reserved_up_to`; epochs in
+    /// `[next_epoch, reserved_up_to)` are already durable and can be handed
+    /// out without another fsync.
+    next_epoch: usize,
+    journal: File,
+}
+
+impl SigningState {
+    /// Opens (creating if absent) the epoch journal at `path` for
+    /// `secret_key`.
+    ///
+    /// On a fresh journal, reservation starts at the key's
+    /// `activation_epoch`. On an existing journal, the persisted
+    /// `reserved_up_to` is honored as a hard floor: epochs below it are
+    /// refused even if they were reserved but never actually signed.
+    ///
+    /// Fails immediately (without blocking) if `path` is already locked by
+    /// another open `SigningState`.
+    pub fn open(path: impl AsRef<Path>, secret_key: SecretKey) -> io::Result<Self> {
+        let (activation_epoch, num_active_epochs) = match &secret_key {
+            SecretKey::Lifetime18Dim64Base8(bytes) => {
+                let sk: <Scheme18 as SignatureScheme>::SecretKey =
+                    secret_key_from_bytes(bytes).ok_or_else(corrupt_secret_key)?;
+                (
+                    <Scheme18 as SignatureScheme>::activation_epoch(&sk),
+                    <Scheme18 as SignatureScheme>::num_active_epochs(&sk),
+                )
+            }
+            SecretKey::Lifetime20Dim64Base8(bytes) => {
+                let sk: <Scheme20 as SignatureScheme>::SecretKey =
+                    secret_key_from_bytes(bytes).ok_or_else(corrupt_secret_key)?;
+                (
+                    <Scheme20 as SignatureScheme>::activation_epoch(&sk),
+                    <Scheme20 as SignatureScheme>::num_active_epochs(&sk),
+                )
+            }
+            SecretKey::Lifetime32Dim64Base8(bytes) => {
+                let sk: <Scheme32 as SignatureScheme>::SecretKey =
+                    secret_key_from_bytes(bytes).ok_or_else(corrupt_secret_key)?;
+                (
+                    <Scheme32 as SignatureScheme>::activation_epoch(&sk),
+                    <Scheme32 as SignatureScheme>::num_active_epochs(&sk),
+                )
+            }
+        };
+
+        let mut journal = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path.as_ref())?;
+
+        // Exclusive, whole-file, advisory lock: two `SigningState`s (in this
+        // process or another) opening the same journal would otherwise both
+        // read the same `reserved_up_to`, both reserve the same batch, and
+        // both hand out the same epoch — the exact one-time-key reuse this
+        // subsystem exists to prevent. Non-blocking: a second opener fails
+        // immediately with an error rather than waiting for the first to
+        // close it (or hanging the FFI call forever).
+        journal.try_lock().map_err(|e| match e {
+            TryLockError::WouldBlock => io::Error::new(
+                io::ErrorKind::WouldBlock,
+                "signing state: journal is already open elsewhere",
+            ),
+            TryLockError::Error(e) => e,
+        })?;
+
+        let reserved_up_to = match read_journal(&mut journal)? {
+            Some(persisted) => persisted.max(activation_epoch),
+            None => {
+                write_journal(&mut journal, activation_epoch)?;
+                activation_epoch
+            }
+        };
+
+        Ok(Self {
+            secret_key,
+            activation_epoch,
+            num_active_epochs,
+            reserved_up_to,
+            next_epoch: reserved_up_to,
+            journal,
+        })
+    }
+
+    fn active_end(&self) -> usize {
+        self.activation_epoch + self.num_active_epochs
+    }
+
+    /// Durably reserves up to `count` additional epochs in a single fsync,
+    /// clamped to the key's active window. Returns the number of epochs
+    /// actually reserved, which may be fewer than `count` (or zero) if the
+    /// window is nearly exhausted.
+    pub fn reserve(&mut self, count: usize) -> io::Result<usize> {
+        let target = self
+            .reserved_up_to
+            .saturating_add(count)
+            .min(self.active_end());
+
+        if target <= self.reserved_up_to {
+            return Ok(0);
+        }
+
+        write_journal(&mut self.journal, target)?;
+        let reserved = target - self.reserved_up_to;
+        self.reserved_up_to = target;
+        Ok(reserved)
+    }
+
+    /// Signs `message` under the next unused epoch, reserving a fresh batch
+    /// (with its own fsync) first if none is already durably reserved.
+    ///
+    /// Refuses (with an error) once the key's active window is exhausted.
+    pub fn sign(&mut self, message: &[u8; MESSAGE_LENGTH]) -> io::Result<Signature> {
+        if self.next_epoch >= self.reserved_up_to {
+            let reserved = self.reserve(RESERVATION_BATCH)?;
+            if reserved == 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    "signing state: active epoch window exhausted",
+                ));
+            }
+        }
+
+        let epoch = self.next_epoch;
+        let sig = match &self.secret_key {
+            SecretKey::Lifetime18Dim64Base8(bytes) => {
+                let sk: <Scheme18 as SignatureScheme>::SecretKey =
+                    secret_key_from_bytes(bytes).ok_or_else(corrupt_secret_key)?;
+                Signature::Lifetime18Dim64Base8(<Scheme18 as SignatureScheme>::sign(&sk, epoch, message))
+            }
+            SecretKey::Lifetime20Dim64Base8(bytes) => {
+                let sk: <Scheme20 as SignatureScheme>::SecretKey =
+                    secret_key_from_bytes(bytes).ok_or_else(corrupt_secret_key)?;
+                Signature::Lifetime20Dim64Base8(<Scheme20 as SignatureScheme>::sign(&sk, epoch, message))
+            }
+            SecretKey::Lifetime32Dim64Base8(bytes) => {
+                let sk: <Scheme32 as SignatureScheme>::SecretKey =
+                    secret_key_from_bytes(bytes).ok_or_else(corrupt_secret_key)?;
+                Signature::Lifetime32Dim64Base8(<Scheme32 as SignatureScheme>::sign(&sk, epoch, message))
+            }
+        };
+        self.next_epoch += 1;
+
+        Ok(sig)
+    }
+}
+
+/// The stored secret-key bytes failed to deserialize back into the typed
+/// key. This should never happen for bytes this module produced itself, but
+/// `SecretKey` can also arrive via `leansig_secret_key_deserialize`, whose
+/// caller-supplied input was only validated once at deserialize time.
+fn corrupt_secret_key() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        "signing state: stored secret key bytes no longer deserialize",
+    )
+}
+
+/// Reads the persisted `reserved_up_to` high-water mark, or `None` if the
+/// journal is newly created (empty).
+fn read_journal(file: &mut File) -> io::Result<Option<usize>> {
+    file.seek(SeekFrom::Start(0))?;
+    let mut buf = [0u8; 8];
+    match file.read_exact(&mut buf) {
+        Ok(()) => Ok(Some(u64::from_le_bytes(buf) as usize)),
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Overwrites the journal with `reserved_up_to` and `fsync`s it before
+/// returning, so the write is durable by the time the caller signs.
+fn write_journal(file: &mut File, reserved_up_to: usize) -> io::Result<()> {
+    file.seek(SeekFrom::Start(0))?;
+    file.write_all(&(reserved_up_to as u64).to_le_bytes())?;
+    file.set_len(8)?;
+    file.sync_all()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secret_key_to_bytes;
+    use rand::{SeedableRng, rngs::StdRng};
+    use std::fs;
+
+    /// A unique, pre-cleaned journal path for a single test, so parallel
+    /// `cargo test` runs don't collide on the same file.
+    fn journal_path(name: &str) -> std::path::PathBuf {
+        let mut path = std::env::temp_dir();
+        path.push(format!(
+            "leansig-signing-state-test-{}-{}",
+            std::process::id(),
+            name
+        ));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    fn test_secret_key(activation_epoch: usize, num_active_epochs: usize) -> SecretKey {
+        let mut rng = StdRng::seed_from_u64(1);
+        let (_, sk) =
+            <Scheme18 as SignatureScheme>::key_gen(&mut rng, activation_epoch, num_active_epochs);
+        SecretKey::Lifetime18Dim64Base8(secret_key_to_bytes(&sk))
+    }
+
+    #[test]
+    fn reopening_after_a_simulated_crash_never_reuses_an_epoch() {
+        let path = journal_path("crash");
+
+        let mut state = SigningState::open(&path, test_secret_key(0, 16)).expect("open");
+        let message = [0u8; MESSAGE_LENGTH];
+
+        // Sign a couple of messages. This auto-reserves a batch (one fsync)
+        // that is larger than what actually gets signed, so most of the
+        // reserved range is still unused in memory.
+        state.sign(&message).expect("sign");
+        state.sign(&message).expect("sign");
+        let reserved_up_to = state.reserved_up_to;
+        let next_epoch = state.next_epoch;
+        assert!(
+            reserved_up_to > next_epoch,
+            "test setup should leave part of the reserved batch unused"
+        );
+
+        // Simulate a crash: the process dies here, after the reservation was
+        // fsync'd but before the rest of the batch was consumed. Drop
+        // in-memory state without any further journal writes, then reopen
+        // from the same file as a fresh process would.
+        drop(state);
+        let reopened = SigningState::open(&path, test_secret_key(0, 16)).expect("reopen");
+
+        // The crash-consistency invariant: no epoch below what was already
+        // durably reserved may ever be handed out again.
+        assert!(reopened.next_epoch >= reserved_up_to);
+        assert_eq!(reopened.reserved_up_to, reserved_up_to);
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn refuses_to_sign_once_the_active_window_is_exhausted() {
+        let path = journal_path("exhausted");
+        let mut state = SigningState::open(&path, test_secret_key(0, 2)).expect("open");
+        let message = [0u8; MESSAGE_LENGTH];
+
+        assert!(state.sign(&message).is_ok());
+        assert!(state.sign(&message).is_ok());
+        assert!(
+            state.sign(&message).is_err(),
+            "signing past activation_epoch + num_active_epochs must be refused"
+        );
+
+        let _ = fs::remove_file(&path);
+    }
+}